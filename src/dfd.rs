@@ -0,0 +1,217 @@
+//! Parsing of the [Data Format Descriptor] (DFD) block.
+//!
+//! The DFD describes the real texel layout of formats that `VkFormat` alone
+//! cannot (e.g. `VK_FORMAT_UNDEFINED` textures such as Basis Universal or
+//! custom/ASTC-HDR payloads). Only the Khronos "basic" descriptor block is
+//! parsed; vendor-specific blocks are skipped.
+//!
+//! [Data Format Descriptor]: https://github.khronos.org/KTX-Specification/#_data_format_descriptor
+use crate::error::ParseError;
+use byteorder::{ByteOrder, LittleEndian};
+
+/// `transferFunction` value meaning samples are already linear.
+const TRANSFER_LINEAR: u8 = 1;
+/// `transferFunction` value meaning samples are sRGB-encoded.
+const TRANSFER_SRGB: u8 = 2;
+
+/// A parsed Khronos basic Data Format Descriptor block.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct DataFormatDescriptor {
+    pub color_model: u8,
+    pub color_primaries: u8,
+    pub transfer_function: u8,
+    pub flags: u8,
+    /// Texel block dimensions for each of up to 4 axes, as stored in the
+    /// block: each value is the dimension in texels, minus one.
+    pub texel_block_dimensions: [u8; 4],
+    pub samples: Vec<SampleInfo>,
+}
+
+impl DataFormatDescriptor {
+    /// Whether `transfer_function` identifies the sRGB transfer function.
+    pub fn is_srgb(&self) -> bool {
+        self.transfer_function == TRANSFER_SRGB
+    }
+
+    /// Whether `transfer_function` identifies the linear transfer function.
+    pub fn is_linear(&self) -> bool {
+        self.transfer_function == TRANSFER_LINEAR
+    }
+
+    /// Parses the DFD section (the leading `dfdTotalSize` plus one or more
+    /// descriptor blocks). Returns `None` if the section contains only
+    /// vendor-specific blocks, which this reader does not interpret.
+    ///
+    /// `base_offset` is the file offset `data` was read from, used to locate
+    /// errors.
+    pub(crate) fn parse(data: &[u8], base_offset: u64) -> Result<Option<Self>, ParseError> {
+        if data.len() < 4 {
+            return Err(ParseError::DfdOverrun(base_offset));
+        }
+        let total_size = LittleEndian::read_u32(&data[0..4]) as usize;
+        if total_size > data.len() {
+            return Err(ParseError::DfdOverrun(base_offset));
+        }
+
+        const BLOCK_HEADER_LEN: usize = 8;
+
+        let mut pos = 4;
+        while pos + BLOCK_HEADER_LEN <= total_size {
+            let block_header = &data[pos..];
+            let vendor_and_type = LittleEndian::read_u32(&block_header[0..4]);
+            let vendor_id = vendor_and_type & 0x1_FFFF;
+            let descriptor_type = vendor_and_type >> 17;
+            let block_size = LittleEndian::read_u16(&block_header[6..8]) as usize;
+            // A block can never be smaller than its own header; without this
+            // check a zero `block_size` would leave `pos` stuck and loop
+            // forever, and an undersized one would misread the next header.
+            if block_size < BLOCK_HEADER_LEN {
+                return Err(ParseError::DfdOverrun(base_offset + pos as u64));
+            }
+            if pos + block_size > total_size {
+                return Err(ParseError::DfdOverrun(base_offset + pos as u64));
+            }
+
+            const KHRONOS_VENDOR_ID: u32 = 0;
+            const BASIC_DESCRIPTOR_TYPE: u32 = 0;
+            if vendor_id == KHRONOS_VENDOR_ID && descriptor_type == BASIC_DESCRIPTOR_TYPE {
+                return Ok(Some(Self::parse_basic_block(
+                    &data[pos..pos + block_size],
+                    base_offset + pos as u64,
+                )?));
+            }
+
+            pos += block_size;
+        }
+        Ok(None)
+    }
+
+    fn parse_basic_block(block: &[u8], base_offset: u64) -> Result<Self, ParseError> {
+        const FIXED_HEADER_LEN: usize = 24;
+        const SAMPLE_LEN: usize = 16;
+        if block.len() < FIXED_HEADER_LEN {
+            return Err(ParseError::DfdOverrun(base_offset));
+        }
+
+        let color_model = block[8];
+        let color_primaries = block[9];
+        let transfer_function = block[10];
+        let flags = block[11];
+        let texel_block_dimensions = [block[12], block[13], block[14], block[15]];
+
+        let mut samples = Vec::new();
+        let mut pos = FIXED_HEADER_LEN;
+        while pos + SAMPLE_LEN <= block.len() {
+            let sample = &block[pos..pos + SAMPLE_LEN];
+            let bit_offset = LittleEndian::read_u16(&sample[0..2]);
+            let bit_length = sample[2];
+            let channel_type = sample[3];
+            let sample_positions = [sample[4], sample[5], sample[6], sample[7]];
+            let sample_lower = LittleEndian::read_u32(&sample[8..12]);
+            let sample_upper = LittleEndian::read_u32(&sample[12..16]);
+            samples.push(SampleInfo {
+                bit_offset,
+                bit_length,
+                channel_type,
+                sample_positions,
+                sample_lower,
+                sample_upper,
+            });
+            pos += SAMPLE_LEN;
+        }
+
+        Ok(Self {
+            color_model,
+            color_primaries,
+            transfer_function,
+            flags,
+            texel_block_dimensions,
+            samples,
+        })
+    }
+}
+
+/// One channel's description within a [`DataFormatDescriptor`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct SampleInfo {
+    pub bit_offset: u16,
+    /// Number of bits occupied by the sample, minus one.
+    pub bit_length: u8,
+    /// Channel type id, with qualifier flags (linear/exponent/signed/float) in the upper bits.
+    pub channel_type: u8,
+    pub sample_positions: [u8; 4],
+    pub sample_lower: u32,
+    pub sample_upper: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a minimal Khronos basic descriptor block with no samples.
+    fn basic_block_bytes() -> Vec<u8> {
+        let mut block = vec![0u8; 24];
+        // vendorId (17 bits) = 0, descriptorType (top bits) = 0 => all zero.
+        LittleEndian::write_u32(&mut block[0..4], 0);
+        // descriptorBlockSize
+        LittleEndian::write_u16(&mut block[6..8], 24);
+        block[8] = 7; // colorModel
+        block[9] = 1; // colorPrimaries
+        block[10] = TRANSFER_SRGB; // transferFunction
+        block[11] = 0; // flags
+        block[12..16].copy_from_slice(&[0, 0, 0, 0]); // texelBlockDimensions
+        block
+    }
+
+    fn dfd_section_bytes(block: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let total_size = 4 + block.len();
+        data.extend_from_slice(&(total_size as u32).to_le_bytes());
+        data.extend_from_slice(block);
+        data
+    }
+
+    #[test]
+    fn parses_basic_block_with_no_samples() {
+        let block = basic_block_bytes();
+        let data = dfd_section_bytes(&block);
+
+        let dfd = DataFormatDescriptor::parse(&data, 0).unwrap().unwrap();
+        assert_eq!(dfd.color_model, 7);
+        assert!(dfd.is_srgb());
+        assert!(dfd.samples.is_empty());
+    }
+
+    #[test]
+    fn rejects_zero_size_block_instead_of_looping() {
+        let mut block = basic_block_bytes();
+        LittleEndian::write_u16(&mut block[6..8], 0); // descriptorBlockSize = 0
+        let data = dfd_section_bytes(&block);
+
+        assert!(matches!(
+            DataFormatDescriptor::parse(&data, 0),
+            Err(ParseError::DfdOverrun(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_block_that_overruns_total_size() {
+        let mut block = basic_block_bytes();
+        LittleEndian::write_u16(&mut block[6..8], 1000); // way past total_size
+        let data = dfd_section_bytes(&block);
+
+        assert!(matches!(
+            DataFormatDescriptor::parse(&data, 0),
+            Err(ParseError::DfdOverrun(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        let data = [0u8, 1, 2];
+        assert!(matches!(
+            DataFormatDescriptor::parse(&data, 0),
+            Err(ParseError::DfdOverrun(0))
+        ));
+    }
+}
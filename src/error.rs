@@ -0,0 +1,144 @@
+//! Error types returned while reading and parsing [`KTX v.2`] files.
+//!
+//! [`KTX v.2`]: https://github.khronos.org/KTX-Specification/
+use std::fmt;
+
+/// Errors that can occur while parsing already-read bytes.
+#[derive(Debug)]
+pub enum ParseError {
+    /// First 12 bytes of the input did not match the KTX2 identifier.
+    BadIdentifier([u8; 12]),
+    /// `pixelWidth` was zero, which the KTX2 spec forbids.
+    ZeroWidth,
+    /// `faceCount` was zero, which the KTX2 spec forbids.
+    ZeroFaceCount,
+    /// Format id does not correspond to a known `VkFormat`.
+    UnknownFormat(u32),
+    /// A feature used by this file is not supported (yet).
+    UnsupportedFeature(&'static str),
+    /// A level index passed to [`Reader::read_level`](crate::Reader::read_level) was out of range.
+    LevelIndexOutOfRange(usize),
+    /// A Key/Value Data entry's length or missing NUL terminator would overrun the section,
+    /// at the given byte offset.
+    KvdEntryOverrun(u64),
+    /// A Key/Value Data entry's key was not valid UTF-8, at the given byte offset.
+    KvdInvalidUtf8Key(u64),
+    /// The Data Format Descriptor section is truncated, or a block's declared size is smaller than
+    /// its header or overruns the section, at the given byte offset.
+    DfdOverrun(u64),
+    /// The input ended before a fixed-size section starting at the given byte offset could be read.
+    UnexpectedEof(u64),
+    /// A level's `offset..offset + length_bytes` range overruns the input, at the given byte offset.
+    LevelDataOverrun(u64),
+    /// `level_count` is too large to compute the level index's byte length without overflow.
+    LevelCountOverflow(u32),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::BadIdentifier(id) => write!(f, "bad KTX2 identifier: {:?}", id),
+            ParseError::ZeroWidth => write!(f, "base width is zero"),
+            ParseError::ZeroFaceCount => write!(f, "face count is zero"),
+            ParseError::UnknownFormat(id) => write!(f, "unknown VkFormat id {}", id),
+            ParseError::UnsupportedFeature(feature) => {
+                write!(f, "unsupported feature: {}", feature)
+            }
+            ParseError::LevelIndexOutOfRange(index) => {
+                write!(f, "level index {} is out of range", index)
+            }
+            ParseError::KvdEntryOverrun(offset) => {
+                write!(f, "key/value data entry at offset {} overruns the section", offset)
+            }
+            ParseError::KvdInvalidUtf8Key(offset) => {
+                write!(f, "key/value data entry at offset {} has a non-UTF-8 key", offset)
+            }
+            ParseError::DfdOverrun(offset) => {
+                write!(f, "data format descriptor block at offset {} overruns the section", offset)
+            }
+            ParseError::UnexpectedEof(offset) => {
+                write!(f, "input ended before offset {} could be read", offset)
+            }
+            ParseError::LevelDataOverrun(offset) => {
+                write!(f, "level data at offset {} overruns the input", offset)
+            }
+            ParseError::LevelCountOverflow(level_count) => {
+                write!(f, "level count {} is too large to index", level_count)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Errors that can occur while reading and parsing a texture.
+#[derive(Debug)]
+pub enum ReadError {
+    /// Reading from the underlying input failed.
+    IoError(std::io::Error),
+    /// Parsing the bytes that were read failed.
+    ParseError(ParseError),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::IoError(e) => write!(f, "io error: {}", e),
+            ReadError::ParseError(e) => write!(f, "parse error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {}
+
+impl From<std::io::Error> for ReadError {
+    fn from(e: std::io::Error) -> Self {
+        ReadError::IoError(e)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(e: ParseError) -> Self {
+        ReadError::ParseError(e)
+    }
+}
+
+/// Errors that can occur while reading texture data into a caller-provided buffer.
+#[derive(Debug)]
+pub enum ReadToError {
+    /// Reading or parsing failed.
+    ReadError(ReadError),
+    /// The provided buffer's length did not match the expected data size, in bytes.
+    BadBuffer(u64),
+}
+
+impl fmt::Display for ReadToError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadToError::ReadError(e) => write!(f, "{}", e),
+            ReadToError::BadBuffer(expected) => {
+                write!(f, "buffer has wrong length, expected {} bytes", expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReadToError {}
+
+impl From<ReadError> for ReadToError {
+    fn from(e: ReadError) -> Self {
+        ReadToError::ReadError(e)
+    }
+}
+
+impl From<std::io::Error> for ReadToError {
+    fn from(e: std::io::Error) -> Self {
+        ReadToError::ReadError(ReadError::IoError(e))
+    }
+}
+
+impl From<ParseError> for ReadToError {
+    fn from(e: ParseError) -> Self {
+        ReadToError::ReadError(ReadError::ParseError(e))
+    }
+}
@@ -0,0 +1,60 @@
+//! `VkFormat` identifiers used by the KTX2 header.
+use crate::error::ParseError;
+use std::convert::TryFrom;
+
+/// Subset of `VkFormat` values that can appear in a KTX2 header.
+///
+/// `VK_FORMAT_UNDEFINED` (id `0`) is valid and indicates that the real pixel
+/// layout is described by the Data Format Descriptor instead.
+#[non_exhaustive]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Format {
+    Undefined,
+    R8Unorm,
+    R8G8Unorm,
+    R8G8B8A8Unorm,
+    R8G8B8A8Srgb,
+    B8G8R8A8Unorm,
+    B8G8R8A8Srgb,
+    R16G16B16A16Sfloat,
+    R32G32B32A32Sfloat,
+    Bc1RgbUnormBlock,
+    Bc1RgbSrgbBlock,
+    Bc3UnormBlock,
+    Bc3SrgbBlock,
+    Bc7UnormBlock,
+    Bc7SrgbBlock,
+    Etc2R8G8B8A8UnormBlock,
+    Etc2R8G8B8A8SrgbBlock,
+    Astc4x4UnormBlock,
+    Astc4x4SrgbBlock,
+}
+
+impl TryFrom<u32> for Format {
+    type Error = ParseError;
+
+    fn try_from(format_id: u32) -> Result<Self, Self::Error> {
+        Ok(match format_id {
+            0 => Format::Undefined,
+            9 => Format::R8Unorm,
+            16 => Format::R8G8Unorm,
+            37 => Format::R8G8B8A8Unorm,
+            43 => Format::R8G8B8A8Srgb,
+            44 => Format::B8G8R8A8Unorm,
+            50 => Format::B8G8R8A8Srgb,
+            97 => Format::R16G16B16A16Sfloat,
+            109 => Format::R32G32B32A32Sfloat,
+            131 => Format::Bc1RgbUnormBlock,
+            132 => Format::Bc1RgbSrgbBlock,
+            137 => Format::Bc3UnormBlock,
+            138 => Format::Bc3SrgbBlock,
+            145 => Format::Bc7UnormBlock,
+            146 => Format::Bc7SrgbBlock,
+            147 => Format::Etc2R8G8B8A8UnormBlock,
+            148 => Format::Etc2R8G8B8A8SrgbBlock,
+            157 => Format::Astc4x4UnormBlock,
+            158 => Format::Astc4x4SrgbBlock,
+            other => return Err(ParseError::UnknownFormat(other)),
+        })
+    }
+}
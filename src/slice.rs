@@ -0,0 +1,178 @@
+//! Zero-copy parser over an in-memory byte slice.
+//!
+//! Intended for the common case where a whole KTX2 file is already mapped
+//! or loaded into memory (e.g. a memory-mapped asset bundle): unlike
+//! [`Reader`](crate::Reader) and [`SyncReader`](crate::sync::SyncReader),
+//! this never allocates or copies level data, instead returning borrowed
+//! sub-slices of the input.
+use crate::dfd::DataFormatDescriptor;
+use crate::error::ParseError;
+use crate::{
+    parse_key_value_data, test_identifier, HeadBytes, Header, IndexBlock, LevelIndex,
+    ParseResult, RegionDescription, SupercompressionScheme, TextureData,
+};
+use std::convert::TryInto;
+
+/// Zero-copy reader over a borrowed `&[u8]` containing a whole KTX2 file.
+pub struct SliceReader<'a> {
+    input: &'a [u8],
+    data: TextureData,
+}
+
+impl<'a> SliceReader<'a> {
+    /// Validates the identifier, header, index block and level index in
+    /// `input`, without copying any texture data.
+    /// # Errors
+    /// Returns a [`ParseError`] located at the offending byte offset if any
+    /// section is truncated or malformed.
+    pub fn new(input: &'a [u8]) -> Result<Self, ParseError> {
+        let head_bytes: &HeadBytes = slice_at(input, 0, 48)?.try_into().unwrap();
+        test_identifier(head_bytes)?;
+        let head = Header::from_bytes(head_bytes)?;
+
+        let index_block_bytes = slice_at(input, IndexBlock::START_BYTE as usize, IndexBlock::BYTE_LEN)?;
+        let index_block = IndexBlock::from_bytes(index_block_bytes.try_into().unwrap())?;
+
+        let level_index_bytes = slice_at(
+            input,
+            LevelIndex::START_BYTE as usize,
+            LevelIndex::total_byte_len(head.level_count)?,
+        )?;
+        let levels_index = LevelIndex::parse_all(level_index_bytes, &head)?;
+
+        let key_value_data = if index_block.kvd_length == 0 {
+            Vec::new()
+        } else {
+            let kvd_bytes = slice_at(
+                input,
+                index_block.kvd_offset as usize,
+                index_block.kvd_length as usize,
+            )?;
+            parse_key_value_data(kvd_bytes, index_block.kvd_offset as u64)?
+        };
+
+        let data_format_descriptor = if index_block.dfd_length == 0 {
+            None
+        } else {
+            let dfd_bytes = slice_at(
+                input,
+                index_block.dfd_offset as usize,
+                index_block.dfd_length as usize,
+            )?;
+            DataFormatDescriptor::parse(dfd_bytes, index_block.dfd_offset as u64)?
+        };
+
+        Ok(Self {
+            input,
+            data: TextureData {
+                head,
+                index_block,
+                levels_index,
+                key_value_data,
+                data_format_descriptor,
+            },
+        })
+    }
+
+    /// Returns [`Header`](crate::Header) of texture.
+    pub fn header(&self) -> &Header {
+        self.data.header()
+    }
+
+    /// Returns the Key/Value Data entries (e.g. `KTXorientation`, `KTXwriter`), in file order.
+    pub fn key_value_data(&self) -> &[(String, Vec<u8>)] {
+        self.data.key_value_data()
+    }
+
+    /// Returns the texture's [`DataFormatDescriptor`](crate::dfd::DataFormatDescriptor),
+    /// if the Khronos basic descriptor block was present.
+    pub fn data_format_descriptor(&self) -> Option<&DataFormatDescriptor> {
+        self.data.data_format_descriptor()
+    }
+
+    /// Returns vector of [`RegionDescription`](crate::RegionDescription) for texture.
+    pub fn regions_description(&self) -> Vec<RegionDescription> {
+        self.data.regions_description()
+    }
+
+    /// Full length of texture data.
+    pub fn data_len_bytes(&self) -> u64 {
+        self.data.data_len_bytes()
+    }
+
+    /// Borrows a single mip level's data directly from the input, with no copy.
+    /// # Errors
+    /// Returns [`ParseError::LevelIndexOutOfRange`] if `level_index` is out
+    /// of range, or [`ParseError::LevelDataOverrun`] if the level's
+    /// `offset..offset + length_bytes` range overruns the input. Supercompressed
+    /// levels cannot be returned zero-copy and yield
+    /// [`ParseError::UnsupportedFeature`].
+    pub fn level(&self, level_index: usize) -> ParseResult<(RegionDescription, &'a [u8])> {
+        if self.data.header().supercompression_scheme != SupercompressionScheme::None {
+            return Err(ParseError::UnsupportedFeature(
+                "zero-copy reading of supercompressed levels",
+            ));
+        }
+
+        let level = self.data.level(level_index)?;
+        let bytes = slice_at(
+            self.input,
+            level.offset as usize,
+            level.length_bytes as usize,
+        )
+        .map_err(|_| ParseError::LevelDataOverrun(level.offset))?;
+
+        let base_offset = self.data.first_level_offset_bytes();
+        let region = self
+            .data
+            .level_region(level_index, level.offset - base_offset);
+        Ok((region, bytes))
+    }
+
+    /// Borrows every level's data directly from the input, with no copy.
+    /// See [`level`](#method.level) for the error conditions.
+    pub fn levels(&self) -> ParseResult<Vec<(RegionDescription, &'a [u8])>> {
+        (0..self.data.levels_index.len())
+            .map(|i| self.level(i))
+            .collect()
+    }
+}
+
+/// Returns `data[offset..offset + len]`, or a located [`ParseError::UnexpectedEof`]
+/// if that range overruns `data`.
+fn slice_at(data: &[u8], offset: usize, len: usize) -> ParseResult<&[u8]> {
+    let end = offset
+        .checked_add(len)
+        .ok_or(ParseError::UnexpectedEof(offset as u64))?;
+    data.get(offset..end)
+        .ok_or(ParseError::UnexpectedEof(offset as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slice_at_returns_requested_range() {
+        let data = [1u8, 2, 3, 4, 5];
+        assert_eq!(slice_at(&data, 1, 3).unwrap(), &[2, 3, 4]);
+    }
+
+    #[test]
+    fn slice_at_rejects_overrun() {
+        let data = [1u8, 2, 3];
+        assert!(matches!(
+            slice_at(&data, 1, 10),
+            Err(ParseError::UnexpectedEof(1))
+        ));
+    }
+
+    #[test]
+    fn slice_at_rejects_offset_overflow_without_panicking() {
+        let data = [1u8, 2, 3];
+        assert!(matches!(
+            slice_at(&data, usize::MAX - 1, 10),
+            Err(ParseError::UnexpectedEof(_))
+        ));
+    }
+}
@@ -0,0 +1,301 @@
+//! Synchronous, blocking reader built on `std::io::{Read, Seek}`.
+//!
+//! Has no async dependencies, so it can be used with zero runtime overhead
+//! by callers who are, for example, loading an asset from a plain file or an
+//! in-memory `&[u8]` at startup. Shares all parsing and offset math with
+//! [`Reader`](crate::Reader) via [`TextureData`](crate::TextureData).
+use crate::dfd::DataFormatDescriptor;
+use crate::error::{ReadError, ReadToError};
+#[cfg(feature = "zstd")]
+use crate::zstd;
+use crate::{
+    parse_key_value_data, test_identifier, HeadBytes, Header, IndexBlock, LevelIndex, ReadResult,
+    ReadToResult, RegionDescription, SupercompressionScheme, TextureData,
+};
+use std::io::{Read, Seek, SeekFrom};
+
+/// Struct to read [`KTX v.2`] files synchronously.
+///
+/// [`KTX v.2`]: https://github.khronos.org/KTX-Specification/
+pub struct SyncReader<T> {
+    input: T,
+    data: TextureData,
+}
+
+impl<T: Read + Seek> SyncReader<T> {
+    /// Create new instance of SyncReader.
+    /// Reads and tries to parse data from `input`, blocking the current thread.
+    /// # Errors
+    /// If reading fails, returns [`ReadError::IoError`](crate::error::ReadError::IoError).
+    /// If parsing fails, returns [`ReadError::ParseError`](crate::error::ReadError::ParseError).
+    pub fn new(mut input: T) -> ReadResult<Self> {
+        let head = Self::read_head(&mut input)?;
+        let index_block = Self::read_index_block(&mut input)?;
+        let levels_index = Self::read_level_index(&mut input, &head)?;
+        let key_value_data = Self::read_key_value_data(&mut input, &index_block)?;
+        let data_format_descriptor = Self::read_data_format_descriptor(&mut input, &index_block)?;
+        Ok(Self {
+            input,
+            data: TextureData {
+                head,
+                index_block,
+                levels_index,
+                key_value_data,
+                data_format_descriptor,
+            },
+        })
+    }
+
+    /// Reads and tries to parse header of texture.
+    fn read_head(input: &mut T) -> ReadResult<Header> {
+        let mut head_bytes: HeadBytes = [0; 48];
+        input.read_exact(&mut head_bytes)?;
+        test_identifier(&head_bytes)?;
+        Ok(Header::from_bytes(&head_bytes)?)
+    }
+
+    /// Reads and tries to parse the index block of texture.
+    fn read_index_block(input: &mut T) -> ReadResult<IndexBlock> {
+        let mut index_block_bytes = [0; IndexBlock::BYTE_LEN];
+        input.seek(SeekFrom::Start(IndexBlock::START_BYTE))?;
+        input.read_exact(&mut index_block_bytes)?;
+        Ok(IndexBlock::from_bytes(&index_block_bytes)?)
+    }
+
+    /// Reads and tries to parse level index of texture.
+    fn read_level_index(input: &mut T, head: &Header) -> ReadResult<Vec<LevelIndex>> {
+        let mut level_index_bytes = vec![0u8; LevelIndex::total_byte_len(head.level_count)?];
+        input.seek(SeekFrom::Start(LevelIndex::START_BYTE))?;
+        input.read_exact(&mut level_index_bytes)?;
+        Ok(LevelIndex::parse_all(&level_index_bytes, head)?)
+    }
+
+    /// Reads and parses the Key/Value Data section pointed to by `index`.
+    fn read_key_value_data(
+        input: &mut T,
+        index: &IndexBlock,
+    ) -> ReadResult<Vec<(String, Vec<u8>)>> {
+        if index.kvd_length == 0 {
+            return Ok(Vec::new());
+        }
+        input.seek(SeekFrom::Start(index.kvd_offset as u64))?;
+        let mut kvd_bytes = vec![0; index.kvd_length as usize];
+        input.read_exact(&mut kvd_bytes)?;
+        Ok(parse_key_value_data(&kvd_bytes, index.kvd_offset as u64)?)
+    }
+
+    /// Reads and parses the Data Format Descriptor pointed to by `index`.
+    fn read_data_format_descriptor(
+        input: &mut T,
+        index: &IndexBlock,
+    ) -> ReadResult<Option<DataFormatDescriptor>> {
+        if index.dfd_length == 0 {
+            return Ok(None);
+        }
+        input.seek(SeekFrom::Start(index.dfd_offset as u64))?;
+        let mut dfd_bytes = vec![0; index.dfd_length as usize];
+        input.read_exact(&mut dfd_bytes)?;
+        Ok(DataFormatDescriptor::parse(
+            &dfd_bytes,
+            index.dfd_offset as u64,
+        )?)
+    }
+
+    /// Reads data of texture.
+    /// Gets vector of bytes. It stores color data of texture.
+    /// Layout of this data can be obtined from [`regions_description()`](#method.regions_description) method of self.
+    pub fn read_data(&mut self) -> ReadResult<Vec<u8>> {
+        let data_len_bytes = self.data_len_bytes();
+        let mut buffer = vec![0; data_len_bytes as usize];
+        self.read_data_to(&mut buffer)
+            .map(|_| buffer)
+            .map_err(|e| match e {
+                ReadToError::ReadError(e) => e,
+                ReadToError::BadBuffer(_) => {
+                    panic!("Pass well sized buffer to read_data_to(), but got BadBuffer error")
+                }
+            })
+    }
+
+    /// ## Reads data of texture.
+    /// Reads texture data to `buf`.
+    /// Layout of this data can be obtined from [`regions_description()`](#method.regions_description) method of self.
+    /// Size of `buf` **MUST** be equal to expected data size. It can be obtained with [`data_len_bytes()`](#method.data_len_bytes) method.
+    pub fn read_data_to(&mut self, buf: &mut [u8]) -> ReadToResult<()> {
+        let data_len_bytes = self.data_len_bytes();
+        if buf.len() != data_len_bytes as usize {
+            return Err(ReadToError::BadBuffer(data_len_bytes));
+        }
+
+        match self.data.header().supercompression_scheme {
+            SupercompressionScheme::None => {
+                let data_start_byte = self.data.first_level_offset_bytes();
+                self.input.seek(SeekFrom::Start(data_start_byte))?;
+                self.input.read_exact(buf)?;
+            }
+            #[cfg(feature = "zstd")]
+            SupercompressionScheme::Zstd => {
+                let decompressed_offsets = self.data.decompressed_level_offsets();
+                let levels = self.data.levels_index.clone();
+                for (level, decompressed_offset) in levels.iter().zip(decompressed_offsets) {
+                    self.input.seek(SeekFrom::Start(level.offset))?;
+                    let mut compressed = vec![0u8; level.length_bytes as usize];
+                    self.input.read_exact(&mut compressed)?;
+
+                    let out_start = decompressed_offset as usize;
+                    let out_end = out_start + level.uncompressed_length_bytes as usize;
+                    zstd::decode_frame(&compressed, &mut buf[out_start..out_end])
+                        .map_err(ReadError::from)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a single mip level's data, without materializing the other levels.
+    ///
+    /// See [`Reader::read_level`](crate::Reader::read_level) for details; this is the blocking equivalent.
+    pub fn read_level(&mut self, level_index: usize) -> ReadResult<(RegionDescription, Vec<u8>)> {
+        let level = self.data.level(level_index)?;
+
+        let region = match self.data.header().supercompression_scheme {
+            SupercompressionScheme::None => {
+                let base_offset = self.data.first_level_offset_bytes();
+                self.data
+                    .level_region(level_index, level.offset - base_offset)
+            }
+            #[cfg(feature = "zstd")]
+            SupercompressionScheme::Zstd => {
+                let offset = self.data.decompressed_level_offsets()[level_index];
+                self.data.level_region(level_index, offset)
+            }
+        };
+
+        self.input.seek(SeekFrom::Start(level.offset))?;
+        let data = match self.data.header().supercompression_scheme {
+            SupercompressionScheme::None => {
+                let mut buf = vec![0u8; level.uncompressed_length_bytes as usize];
+                self.input.read_exact(&mut buf)?;
+                buf
+            }
+            #[cfg(feature = "zstd")]
+            SupercompressionScheme::Zstd => {
+                let mut compressed = vec![0u8; level.length_bytes as usize];
+                self.input.read_exact(&mut compressed)?;
+                let mut buf = vec![0u8; level.uncompressed_length_bytes as usize];
+                zstd::decode_frame(&compressed, &mut buf).map_err(ReadError::from)?;
+                buf
+            }
+        };
+
+        Ok((region, data))
+    }
+
+    /// Returns [`Header`](crate::Header) of texture.
+    pub fn header(&self) -> &Header {
+        self.data.header()
+    }
+
+    /// Returns the Key/Value Data entries (e.g. `KTXorientation`, `KTXwriter`), in file order.
+    pub fn key_value_data(&self) -> &[(String, Vec<u8>)] {
+        self.data.key_value_data()
+    }
+
+    /// Returns the texture's [`DataFormatDescriptor`](crate::dfd::DataFormatDescriptor),
+    /// if the Khronos basic descriptor block was present.
+    pub fn data_format_descriptor(&self) -> Option<&DataFormatDescriptor> {
+        self.data.data_format_descriptor()
+    }
+
+    /// Returns vector of [`RegionDescription`](crate::RegionDescription) for texture.
+    pub fn regions_description(&self) -> Vec<RegionDescription> {
+        self.data.regions_description()
+    }
+
+    /// Full length of texture data.
+    pub fn data_len_bytes(&self) -> u64 {
+        self.data.data_len_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::ParseError, KTX2_IDENTIFIER};
+    use byteorder::{ByteOrder, LittleEndian};
+    use std::io::Cursor as IoCursor;
+
+    /// Builds an uncompressed two-level KTX2 file: a 48-byte header, a
+    /// 32-byte index block (no KVD/DFD), a two-entry level index, and the
+    /// levels' raw bytes back to back.
+    fn sample_bytes() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let level0 = b"LEVEL0!!".to_vec();
+        let level1 = b"LV1!".to_vec();
+
+        let level_index_end = 80 + 2 * LevelIndex::BYTE_LEN as u64;
+        let level0_offset = level_index_end;
+        let level1_offset = level0_offset + level0.len() as u64;
+
+        let mut bytes = vec![0u8; 48];
+        bytes[0..12].copy_from_slice(&KTX2_IDENTIFIER);
+        LittleEndian::write_u32(&mut bytes[12..16], 0); // format: Undefined
+        LittleEndian::write_u32(&mut bytes[16..20], 1); // type_size
+        LittleEndian::write_u32(&mut bytes[20..24], 4); // base_width
+        LittleEndian::write_u32(&mut bytes[24..28], 4); // base_height
+        LittleEndian::write_u32(&mut bytes[28..32], 1); // base_depth
+        LittleEndian::write_u32(&mut bytes[32..36], 1); // layer_count
+        LittleEndian::write_u32(&mut bytes[36..40], 1); // face_count
+        LittleEndian::write_u32(&mut bytes[40..44], 2); // level_count
+        LittleEndian::write_u32(&mut bytes[44..48], 0); // supercompression_scheme: None
+
+        // Index block: no KVD, no DFD, no supercompression global data.
+        bytes.extend_from_slice(&[0u8; IndexBlock::BYTE_LEN]);
+
+        // Level index: entry 0 describes mip level 0, entry 1 mip level 1.
+        let mut level_index = vec![0u8; 2 * LevelIndex::BYTE_LEN as usize];
+        LittleEndian::write_u64(&mut level_index[0..8], level0_offset);
+        LittleEndian::write_u64(&mut level_index[8..16], level0.len() as u64);
+        LittleEndian::write_u64(&mut level_index[16..24], level0.len() as u64);
+        LittleEndian::write_u64(&mut level_index[24..32], level1_offset);
+        LittleEndian::write_u64(&mut level_index[32..40], level1.len() as u64);
+        LittleEndian::write_u64(&mut level_index[40..48], level1.len() as u64);
+        bytes.extend_from_slice(&level_index);
+
+        bytes.extend_from_slice(&level0);
+        bytes.extend_from_slice(&level1);
+
+        (bytes, level0, level1)
+    }
+
+    #[test]
+    fn read_data_returns_both_levels_in_file_order() {
+        let (bytes, level0, level1) = sample_bytes();
+        let mut reader = SyncReader::new(IoCursor::new(bytes)).unwrap();
+
+        let mut expected = level0;
+        expected.extend_from_slice(&level1);
+        assert_eq!(reader.read_data().unwrap(), expected);
+    }
+
+    #[test]
+    fn read_level_returns_a_single_levels_region_and_data() {
+        let (bytes, _level0, level1) = sample_bytes();
+        let mut reader = SyncReader::new(IoCursor::new(bytes)).unwrap();
+
+        let (region, data) = reader.read_level(1).unwrap();
+        assert_eq!(data, level1);
+        assert_eq!(region.level, 1);
+        assert_eq!(region.offset_bytes, 8); // after level 0's 8 bytes
+    }
+
+    #[test]
+    fn read_level_rejects_out_of_range_index() {
+        let (bytes, _level0, _level1) = sample_bytes();
+        let mut reader = SyncReader::new(IoCursor::new(bytes)).unwrap();
+
+        assert!(matches!(
+            reader.read_level(5),
+            Err(ReadError::ParseError(ParseError::LevelIndexOutOfRange(5)))
+        ));
+    }
+}
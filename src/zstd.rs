@@ -0,0 +1,1021 @@
+//! Minimal pure-Rust Zstandard frame decoder.
+//!
+//! Implements the subset of the [Zstandard format] needed to decode the
+//! independently-compressed mip levels produced by KTX2's
+//! `supercompressionScheme == 2`: frame header parsing, the `Raw`, `RLE` and
+//! `Compressed` block types, FSE-coded sequences (predefined, RLE,
+//! FSE-compressed and repeat modes) and Huffman-coded literals (direct and
+//! FSE-compressed weights, single- and 4-stream). Dictionaries and
+//! long-distance matching are not supported; a frame's content checksum is
+//! skipped without being verified.
+//!
+//! **Experimental:** the FSE table-description ([`read_ncount`]) and the
+//! per-sequence bit interleaving in [`decode_sequences_section`] were
+//! written against the RFC 8878 text rather than against a reference
+//! decoder, since this crate has no access to one (or to real Zstandard
+//! fixtures) in every environment it builds in. `Raw`/`RLE` blocks are
+//! covered by tests against hand-built frames; the `Compressed_Block` path
+//! (the one real `zstd`-compressed files actually use) is not, and several
+//! non-trivial bugs in it (a malformed default FSE distribution, wrong
+//! sequence bit-read/update order, a too-narrow match-offset window) were
+//! already found this way and fixed by re-reading the spec rather than by
+//! a failing test — a strong signal more remain. Cross-check this path
+//! against `zstd`'s reference implementation, or fuzz it with real
+//! `zstd -19`-compressed input, before depending on it for anything beyond
+//! the `Raw`/`RLE` path.
+//!
+//! [Zstandard format]: https://datatracker.ietf.org/doc/html/rfc8878
+use crate::error::ParseError;
+
+const MAGIC_NUMBER: u32 = 0xFD2FB528;
+
+/// State carried across the blocks of a single frame: the Huffman table and
+/// the sequence FSE tables persist across blocks (for
+/// `Treeless_Literals_Block` and `Repeat_Mode` sequences), and the three
+/// repeat offsets persist and evolve across every sequence in the frame.
+struct FrameState {
+    huffman: Option<HuffmanTable>,
+    seq_tables: Option<[FseTable; 3]>,
+    rep_offsets: [u64; 3],
+}
+
+impl FrameState {
+    fn new() -> Self {
+        Self {
+            huffman: None,
+            seq_tables: None,
+            rep_offsets: [1, 4, 8],
+        }
+    }
+}
+
+/// Decodes a single Zstandard frame, block by block up to and including its
+/// last block, and writes the result into `out`.
+///
+/// `out` must already be sized to the frame's decompressed length. A frame
+/// that would write past the end of `out` is an error; a frame that ends
+/// before filling `out` leaves the remaining bytes unchanged.
+pub(crate) fn decode_frame(data: &[u8], out: &mut [u8]) -> Result<(), ParseError> {
+    let mut cursor = Cursor::new(data);
+    let magic = cursor.read_u32()?;
+    if magic != MAGIC_NUMBER {
+        return Err(ParseError::UnsupportedFeature("zstd: not a Zstandard frame"));
+    }
+
+    let descriptor = cursor.read_u8()?;
+    let single_segment = descriptor & 0b0010_0000 != 0;
+    let has_checksum = descriptor & 0b0000_0100 != 0;
+    let dict_id_flag = descriptor & 0b0000_0011;
+    let fcs_flag = descriptor >> 6;
+
+    if !single_segment {
+        // Window_Descriptor byte.
+        cursor.read_u8()?;
+    }
+
+    let dict_id_len = match dict_id_flag {
+        0 => 0,
+        1 => 1,
+        2 => 2,
+        3 => 4,
+        _ => unreachable!(),
+    };
+    if dict_id_len != 0 {
+        return Err(ParseError::UnsupportedFeature("zstd: dictionaries"));
+    }
+    cursor.skip(dict_id_len)?;
+
+    let fcs_len = match (fcs_flag, single_segment) {
+        (0, false) => 0,
+        (0, true) => 1,
+        (1, _) => 2,
+        (2, _) => 4,
+        (3, _) => 8,
+        _ => unreachable!(),
+    };
+    if fcs_len != 0 {
+        // We already know the decompressed size from the KTX2 level index,
+        // so the value itself is only read to advance the cursor.
+        cursor.skip(fcs_len)?;
+    }
+
+    let mut state = FrameState::new();
+    let mut written = 0usize;
+    loop {
+        let header = cursor.read_u24()?;
+        let last_block = header & 1 != 0;
+        let block_type = (header >> 1) & 0b11;
+        let block_size = (header >> 3) as usize;
+
+        written += match block_type {
+            0 => decode_raw_block(&mut cursor, block_size, &mut out[written..])?,
+            1 => decode_rle_block(&mut cursor, block_size, &mut out[written..])?,
+            2 => decode_compressed_block(&mut cursor, block_size, out, written, &mut state)?,
+            _ => return Err(ParseError::UnsupportedFeature("zstd: reserved block type")),
+        };
+
+        if last_block {
+            break;
+        }
+    }
+
+    if has_checksum {
+        cursor.skip(4)?;
+    }
+
+    Ok(())
+}
+
+/// Checks that writing `n` more bytes at `produced` still fits within a
+/// buffer of `cap` bytes, returning a located error instead of letting the
+/// caller's indexing panic on a corrupt block/sequence length.
+fn check_fits(produced: usize, n: usize, cap: usize) -> Result<(), ParseError> {
+    if produced.checked_add(n).map_or(true, |end| end > cap) {
+        return Err(ParseError::UnsupportedFeature(
+            "zstd: decoded output overruns the level's declared size",
+        ));
+    }
+    Ok(())
+}
+
+fn decode_raw_block(cursor: &mut Cursor, block_size: usize, out: &mut [u8]) -> Result<usize, ParseError> {
+    check_fits(0, block_size, out.len())?;
+    let bytes = cursor.read_bytes(block_size)?;
+    out[..block_size].copy_from_slice(bytes);
+    Ok(block_size)
+}
+
+fn decode_rle_block(cursor: &mut Cursor, block_size: usize, out: &mut [u8]) -> Result<usize, ParseError> {
+    check_fits(0, block_size, out.len())?;
+    let byte = cursor.read_u8()?;
+    out[..block_size].fill(byte);
+    Ok(block_size)
+}
+
+/// Decodes a `Compressed_Block`: a literals section (raw, RLE, Huffman or
+/// treeless-Huffman) followed by a sequences section (FSE-coded
+/// literal-length/offset/match-length triples), replayed against `out` to
+/// produce this block's share of decompressed data starting at
+/// `written_so_far`.
+fn decode_compressed_block(
+    cursor: &mut Cursor,
+    block_size: usize,
+    out: &mut [u8],
+    written_so_far: usize,
+    state: &mut FrameState,
+) -> Result<usize, ParseError> {
+    let block = cursor.read_bytes(block_size)?;
+    let mut block_cursor = Cursor::new(block);
+
+    let literals = decode_literals_section(&mut block_cursor, state)?;
+    let remaining = &block[block_cursor.pos..];
+    let sequences = decode_sequences_section(remaining, state)?;
+
+    if sequences.is_empty() {
+        check_fits(written_so_far, literals.len(), out.len())?;
+        out[written_so_far..written_so_far + literals.len()].copy_from_slice(&literals);
+        return Ok(literals.len());
+    }
+
+    let mut produced = written_so_far;
+    let mut lit_pos = 0usize;
+    for seq in &sequences {
+        let lit_len = seq.literal_length as usize;
+        if lit_pos + lit_len > literals.len() {
+            return Err(ParseError::UnsupportedFeature(
+                "zstd: sequence consumes more literals than the block decoded",
+            ));
+        }
+        check_fits(produced, lit_len, out.len())?;
+        out[produced..produced + lit_len].copy_from_slice(&literals[lit_pos..lit_pos + lit_len]);
+        lit_pos += lit_len;
+        produced += lit_len;
+
+        let offset = resolve_offset(seq.offset_value, seq.literal_length, &mut state.rep_offsets);
+        if offset == 0 || offset as usize > produced {
+            // A zero or out-of-window offset can never be valid output of a
+            // real encoder; treat it as corrupt input rather than panicking
+            // on the indexing below.
+            return Err(ParseError::UnsupportedFeature("zstd: invalid match offset"));
+        }
+        let offset = offset as usize;
+        let match_len = seq.match_length as usize;
+        check_fits(produced, match_len, out.len())?;
+        for i in 0..match_len {
+            out[produced + i] = out[produced + i - offset];
+        }
+        produced += match_len;
+    }
+
+    let tail = &literals[lit_pos..];
+    check_fits(produced, tail.len(), out.len())?;
+    out[produced..produced + tail.len()].copy_from_slice(tail);
+    produced += tail.len();
+
+    Ok(produced - written_so_far)
+}
+
+/// Resolves an `Offset_Value` decoded from the Offset FSE table into an
+/// actual back-reference distance, applying the repeat-offset rules of RFC
+/// 8878 section 3.1.1.1 and updating `rep_offsets` for the next sequence.
+fn resolve_offset(offset_value: u64, literal_length: u32, rep_offsets: &mut [u64; 3]) -> u64 {
+    if offset_value > 3 {
+        let offset = offset_value - 3;
+        rep_offsets[2] = rep_offsets[1];
+        rep_offsets[1] = rep_offsets[0];
+        rep_offsets[0] = offset;
+        return offset;
+    }
+
+    let mut idx = offset_value as usize;
+    if literal_length == 0 {
+        idx += 1;
+    }
+    match idx {
+        1 => rep_offsets[0],
+        2 => {
+            let offset = rep_offsets[1];
+            rep_offsets[1] = rep_offsets[0];
+            rep_offsets[0] = offset;
+            offset
+        }
+        3 => {
+            let offset = rep_offsets[2];
+            rep_offsets[2] = rep_offsets[1];
+            rep_offsets[1] = rep_offsets[0];
+            rep_offsets[0] = offset;
+            offset
+        }
+        _ => {
+            let offset = rep_offsets[0].saturating_sub(1).max(1);
+            rep_offsets[2] = rep_offsets[1];
+            rep_offsets[1] = rep_offsets[0];
+            rep_offsets[0] = offset;
+            offset
+        }
+    }
+}
+
+// --- Literals section ----------------------------------------------------
+
+fn decode_literals_section(
+    cursor: &mut Cursor,
+    state: &mut FrameState,
+) -> Result<Vec<u8>, ParseError> {
+    let literals_header = cursor.read_u8()?;
+    let literals_block_type = literals_header & 0b11;
+    let size_format = (literals_header >> 2) & 0b11;
+
+    match literals_block_type {
+        0 | 1 => {
+            let regenerated_size = match size_format {
+                0 | 2 => (literals_header >> 3) as usize,
+                1 => {
+                    let b1 = cursor.read_u8()? as usize;
+                    ((literals_header >> 4) as usize) | (b1 << 4)
+                }
+                // Raw/RLE literals blocks only define 1-byte headers for
+                // size_format 0/2 and a 2-byte header for 1; size_format 3 is
+                // reserved for Compressed_Literals_Block (handled below), so
+                // a Raw/RLE block claiming it is malformed input.
+                _ => {
+                    return Err(ParseError::UnsupportedFeature(
+                        "zstd: invalid size_format for raw/RLE literals block",
+                    ))
+                }
+            };
+            Ok(match literals_block_type {
+                0 => cursor.read_bytes(regenerated_size)?.to_vec(),
+                1 => vec![cursor.read_u8()?; regenerated_size],
+                _ => unreachable!(),
+            })
+        }
+        2 | 3 => {
+            let (regenerated_size, compressed_size, n_streams) = match size_format {
+                0 => {
+                    let v = (literals_header as u32)
+                        | (cursor.read_u8()? as u32) << 8
+                        | (cursor.read_u8()? as u32) << 16;
+                    ((v >> 4) & 0x3FF, (v >> 14) & 0x3FF, 1)
+                }
+                1 => {
+                    let v = (literals_header as u32)
+                        | (cursor.read_u8()? as u32) << 8
+                        | (cursor.read_u8()? as u32) << 16
+                        | (cursor.read_u8()? as u32) << 24;
+                    ((v >> 4) & 0x3FFF, (v >> 18) & 0x3FFF, 4)
+                }
+                2 | 3 => {
+                    let mut v: u64 = literals_header as u64;
+                    for shift in [8u32, 16, 24, 32] {
+                        v |= (cursor.read_u8()? as u64) << shift;
+                    }
+                    (((v >> 4) & 0x3FFFF) as u32, ((v >> 22) & 0x3FFFF) as u32, 4)
+                }
+                _ => unreachable!(),
+            };
+            let regenerated_size = regenerated_size as usize;
+            let compressed_size = compressed_size as usize;
+
+            if literals_block_type == 2 {
+                let huf_bytes = cursor.read_bytes(compressed_size)?;
+                let (table, header_len) = HuffmanTable::parse(huf_bytes)?;
+                state.huffman = Some(table);
+                decode_huffman_streams(
+                    &huf_bytes[header_len..],
+                    state.huffman.as_ref().unwrap(),
+                    regenerated_size,
+                    n_streams,
+                )
+            } else {
+                let huf_bytes = cursor.read_bytes(compressed_size)?;
+                let table = state.huffman.as_ref().ok_or(ParseError::UnsupportedFeature(
+                    "zstd: treeless literals block with no preceding Huffman table",
+                ))?;
+                decode_huffman_streams(huf_bytes, table, regenerated_size, n_streams)
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn decode_huffman_streams(
+    data: &[u8],
+    table: &HuffmanTable,
+    regenerated_size: usize,
+    n_streams: u32,
+) -> Result<Vec<u8>, ParseError> {
+    if n_streams == 1 {
+        return table.decode(data, regenerated_size);
+    }
+
+    if data.len() < 6 {
+        return Err(ParseError::UnsupportedFeature("zstd: truncated jump table"));
+    }
+    let size1 = u16::from_le_bytes([data[0], data[1]]) as usize;
+    let size2 = u16::from_le_bytes([data[2], data[3]]) as usize;
+    let size3 = u16::from_le_bytes([data[4], data[5]]) as usize;
+    let streams = &data[6..];
+    let total = size1
+        .checked_add(size2)
+        .and_then(|v| v.checked_add(size3))
+        .ok_or(ParseError::UnsupportedFeature("zstd: truncated jump table"))?;
+    if total > streams.len() {
+        return Err(ParseError::UnsupportedFeature("zstd: truncated jump table"));
+    }
+    let (s1, rest) = streams.split_at(size1);
+    let (s2, rest) = rest.split_at(size2);
+    let (s3, s4) = rest.split_at(size3);
+
+    let regen1 = (regenerated_size + 3) / 4;
+    let regen4 = regenerated_size.saturating_sub(regen1 * 3);
+
+    let mut out = Vec::with_capacity(regenerated_size);
+    out.extend(table.decode(s1, regen1)?);
+    out.extend(table.decode(s2, regen1)?);
+    out.extend(table.decode(s3, regen1)?);
+    out.extend(table.decode(s4, regen4)?);
+    Ok(out)
+}
+
+// --- Sequences section -----------------------------------------------------
+
+struct Sequence {
+    literal_length: u32,
+    offset_value: u64,
+    match_length: u32,
+}
+
+fn decode_sequences_section(
+    data: &[u8],
+    state: &mut FrameState,
+) -> Result<Vec<Sequence>, ParseError> {
+    if data.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut fwd = ForwardBitReader::new(data);
+    let byte0 = fwd.read_byte()?;
+    let nb_seq: u32 = if byte0 == 0 {
+        return Ok(Vec::new());
+    } else if byte0 < 128 {
+        byte0 as u32
+    } else if byte0 < 255 {
+        let byte1 = fwd.read_byte()? as u32;
+        ((byte0 as u32 - 128) << 8) + byte1
+    } else {
+        let lo = fwd.read_byte()? as u32;
+        let hi = fwd.read_byte()? as u32;
+        (lo | (hi << 8)) + 0x7F00
+    };
+
+    let modes = fwd.read_byte()?;
+    let ll_mode = (modes >> 6) & 0b11;
+    let of_mode = (modes >> 4) & 0b11;
+    let ml_mode = (modes >> 2) & 0b11;
+
+    let previous = state.seq_tables.take();
+    let ll_table = build_seq_table(ll_mode, &mut fwd, SeqAlphabet::LiteralLength, &previous)?;
+    let of_table = build_seq_table(of_mode, &mut fwd, SeqAlphabet::Offset, &previous)?;
+    let ml_table = build_seq_table(ml_mode, &mut fwd, SeqAlphabet::MatchLength, &previous)?;
+
+    let header_len = fwd.bytes_consumed();
+    let bitstream = &data[header_len..];
+    let mut bits = ReverseBitReader::new(bitstream)?;
+
+    let mut ll_state = bits.read(ll_table.accuracy_log as u32) as usize;
+    let mut of_state = bits.read(of_table.accuracy_log as u32) as usize;
+    let mut ml_state = bits.read(ml_table.accuracy_log as u32) as usize;
+
+    let mut sequences = Vec::with_capacity(nb_seq as usize);
+    for i in 0..nb_seq {
+        let ll_code = ll_table.entries[ll_state].0;
+        let of_code = of_table.entries[of_state].0;
+        let ml_code = ml_table.entries[ml_state].0;
+
+        // Per RFC 8878 section 3.1.1.3.2.1.1, a sequence's extra bits are
+        // read from the shared bitstream in Offset, Match_Length,
+        // Literal_Length order (the reverse of how the three symbols are
+        // decoded), not the LL/OF/ML order the symbols are listed in above.
+        let offset_value = (1u64 << of_code) + bits.read(of_code as u32) as u64;
+        let match_length =
+            ML_BASELINE[ml_code as usize] + bits.read(ML_EXTRA_BITS[ml_code as usize] as u32);
+        let literal_length =
+            LL_BASELINE[ll_code as usize] + bits.read(LL_EXTRA_BITS[ll_code as usize] as u32);
+
+        sequences.push(Sequence {
+            literal_length,
+            offset_value,
+            match_length,
+        });
+
+        if i + 1 != nb_seq {
+            // FSE state updates follow the opposite order, LL, ML, OF.
+            let (_, nb_bits, base) = ll_table.entries[ll_state];
+            ll_state = base as usize + bits.read(nb_bits as u32) as usize;
+            let (_, nb_bits, base) = ml_table.entries[ml_state];
+            ml_state = base as usize + bits.read(nb_bits as u32) as usize;
+            let (_, nb_bits, base) = of_table.entries[of_state];
+            of_state = base as usize + bits.read(nb_bits as u32) as usize;
+        }
+    }
+
+    state.seq_tables = Some([ll_table, of_table, ml_table]);
+    Ok(sequences)
+}
+
+#[derive(Clone, Copy)]
+enum SeqAlphabet {
+    LiteralLength,
+    Offset,
+    MatchLength,
+}
+
+impl SeqAlphabet {
+    fn max_symbol(self) -> usize {
+        match self {
+            SeqAlphabet::LiteralLength => 35,
+            SeqAlphabet::Offset => 31,
+            SeqAlphabet::MatchLength => 52,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            SeqAlphabet::LiteralLength => 0,
+            SeqAlphabet::Offset => 1,
+            SeqAlphabet::MatchLength => 2,
+        }
+    }
+
+    fn default_table(self) -> FseTable {
+        match self {
+            SeqAlphabet::LiteralLength => FseTable::from_normalized(&LL_DEFAULT_DISTRIBUTION, 6),
+            SeqAlphabet::Offset => FseTable::from_normalized(&OF_DEFAULT_DISTRIBUTION, 5),
+            SeqAlphabet::MatchLength => FseTable::from_normalized(&ML_DEFAULT_DISTRIBUTION, 6),
+        }
+    }
+}
+
+fn build_seq_table(
+    mode: u8,
+    fwd: &mut ForwardBitReader,
+    alphabet: SeqAlphabet,
+    previous: &Option<[FseTable; 3]>,
+) -> Result<FseTable, ParseError> {
+    match mode {
+        0 => Ok(alphabet.default_table()),
+        1 => {
+            let symbol = fwd.read_byte()?;
+            Ok(FseTable::rle(symbol))
+        }
+        2 => {
+            let (norm, log) = read_ncount(fwd, alphabet.max_symbol())?;
+            // A table description is padded to a whole byte; skip the padding
+            // so the next table (or `read_byte`) starts on a byte boundary.
+            fwd.bit_pos = fwd.bytes_consumed() * 8;
+            Ok(FseTable::from_normalized(&norm, log))
+        }
+        3 => {
+            let tables = previous.as_ref().ok_or(ParseError::UnsupportedFeature(
+                "zstd: repeat_mode sequence table with no preceding block",
+            ))?;
+            Ok(tables[alphabet.index()].clone())
+        }
+        _ => unreachable!(),
+    }
+}
+
+// --- FSE -------------------------------------------------------------------
+
+#[derive(Clone)]
+struct FseTable {
+    /// `entries[state] = (symbol, update_bits, update_base)`.
+    entries: Vec<(u8, u8, u16)>,
+    accuracy_log: u8,
+}
+
+impl FseTable {
+    fn rle(symbol: u8) -> Self {
+        Self {
+            entries: vec![(symbol, 0, 0)],
+            accuracy_log: 0,
+        }
+    }
+
+    /// Builds an FSE decoding table from a normalized distribution (RFC 8878
+    /// section 4.1, "From normalized distribution to decoding tables").
+    fn from_normalized(norm: &[i32], accuracy_log: u8) -> Self {
+        let table_size = 1usize << accuracy_log;
+        let mut symbol_of_cell = vec![0u8; table_size];
+        let mut high_threshold = table_size - 1;
+
+        for (symbol, &count) in norm.iter().enumerate() {
+            if count == -1 {
+                symbol_of_cell[high_threshold] = symbol as u8;
+                high_threshold -= 1;
+            }
+        }
+
+        let step = (table_size >> 1) + (table_size >> 3) + 3;
+        let mask = table_size - 1;
+        let mut pos = 0usize;
+        for (symbol, &count) in norm.iter().enumerate() {
+            if count <= 0 {
+                continue;
+            }
+            for _ in 0..count {
+                symbol_of_cell[pos] = symbol as u8;
+                pos = (pos + step) & mask;
+                while pos > high_threshold {
+                    pos = (pos + step) & mask;
+                }
+            }
+        }
+
+        let mut next_state_for_symbol = vec![0u32; norm.len()];
+        for (symbol, &count) in norm.iter().enumerate() {
+            next_state_for_symbol[symbol] = if count < 0 { 1 } else { count as u32 };
+        }
+
+        let mut entries = Vec::with_capacity(table_size);
+        for &symbol in &symbol_of_cell {
+            let next_state = next_state_for_symbol[symbol as usize];
+            next_state_for_symbol[symbol as usize] += 1;
+            let nb_bits = accuracy_log - highest_bit(next_state);
+            let base = ((next_state << nb_bits) as i64 - table_size as i64) as u16;
+            entries.push((symbol, nb_bits, base));
+        }
+
+        Self {
+            entries,
+            accuracy_log,
+        }
+    }
+}
+
+fn highest_bit(v: u32) -> u8 {
+    31 - v.leading_zeros() as u8
+}
+
+/// Decodes an FSE table description (RFC 8878 section 4.1.1): an accuracy
+/// log followed by a variable-length code per symbol, read as a forward
+/// (not reversed) bitstream.
+fn read_ncount(fwd: &mut ForwardBitReader, max_symbol: usize) -> Result<(Vec<i32>, u8), ParseError> {
+    let accuracy_log = 5 + fwd.read(4) as u8;
+    if accuracy_log > 15 {
+        return Err(ParseError::UnsupportedFeature("zstd: FSE accuracy log too large"));
+    }
+
+    let mut remaining: i32 = (1i32 << accuracy_log) + 1;
+    let mut threshold: i32 = 1i32 << accuracy_log;
+    let mut bits_to_read = accuracy_log + 1;
+    let mut norm = vec![0i32; max_symbol + 1];
+    let mut symbol = 0usize;
+
+    while remaining > 1 && symbol <= max_symbol {
+        let max_val = (2 * threshold - 1) - remaining;
+        let low_bits = bits_to_read - 1;
+        let low = fwd.read(low_bits as u32) as i32;
+        let value = if low < max_val {
+            low
+        } else {
+            let top = fwd.read(1) as i32;
+            let candidate = low + (top << low_bits);
+            if candidate >= (1 << bits_to_read) {
+                candidate - max_val
+            } else {
+                candidate
+            }
+        };
+
+        let proba = value - 1;
+        norm[symbol] = proba;
+        symbol += 1;
+        remaining -= proba.abs();
+
+        if proba == 0 {
+            loop {
+                let repeat = fwd.read(2);
+                symbol += repeat as usize;
+                if repeat != 3 {
+                    break;
+                }
+            }
+        }
+
+        while threshold > remaining {
+            bits_to_read -= 1;
+            threshold >>= 1;
+        }
+    }
+
+    Ok((norm, accuracy_log))
+}
+
+/// Default Literals Length distribution, tableLog 6 (RFC 8878 appendix).
+const LL_DEFAULT_DISTRIBUTION: [i32; 36] = [
+    4, 3, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 3, 2, 1, 1, 1, 1, 1,
+    -1, -1, -1, -1,
+];
+/// Default Match Length distribution, tableLog 6 (RFC 8878 appendix).
+const ML_DEFAULT_DISTRIBUTION: [i32; 53] = [
+    1, 4, 3, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1, -1, -1,
+];
+/// Default Offset Code distribution, tableLog 5 (RFC 8878 appendix).
+const OF_DEFAULT_DISTRIBUTION: [i32; 29] = [
+    1, 1, 1, 1, 1, 1, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1,
+];
+
+/// `LL_BASELINE[code] + readBits(LL_EXTRA_BITS[code])` gives the actual
+/// literal length for a Literal_Length_Code (RFC 8878 appendix).
+const LL_BASELINE: [u32; 36] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 18, 20, 22, 24, 28, 32, 40, 48, 64,
+    128, 256, 512, 1024, 2048, 4096, 8192, 16384, 32768, 65536,
+];
+const LL_EXTRA_BITS: [u8; 36] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 3, 3, 4, 6, 7, 8, 9, 10, 11,
+    12, 13, 14, 15, 16,
+];
+/// `ML_BASELINE[code] + readBits(ML_EXTRA_BITS[code])` gives the actual
+/// match length for a Match_Length_Code (RFC 8878 appendix).
+const ML_BASELINE: [u32; 53] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27,
+    28, 29, 30, 31, 32, 33, 34, 35, 37, 39, 41, 43, 47, 51, 59, 67, 83, 99, 131, 259, 515, 1027,
+    2051, 4099, 8195, 16387, 32771, 65539,
+];
+const ML_EXTRA_BITS: [u8; 53] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 1, 1, 1, 2, 2, 3, 3, 4, 4, 5, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+];
+
+// --- Huffman ----------------------------------------------------------------
+
+/// A canonical Huffman decoding table built from per-symbol bit weights
+/// (RFC 8878 section 4.2).
+struct HuffmanTable {
+    /// `table[bits] = (symbol, nb_bits)`, indexed by the next `table_log`
+    /// bits of the bitstream.
+    table: Vec<(u8, u8)>,
+    table_log: u8,
+}
+
+const HUF_MAX_WEIGHT: usize = 11;
+
+impl HuffmanTable {
+    /// Parses a `Huffman_Tree_Description` at the start of `data`, returning
+    /// the table and the number of bytes the description occupied.
+    fn parse(data: &[u8]) -> Result<(Self, usize), ParseError> {
+        let header = *data
+            .first()
+            .ok_or(ParseError::UnsupportedFeature("zstd: truncated Huffman table"))?;
+        if header >= 128 {
+            let n_symbols = header as usize - 127;
+            let weight_bytes = (n_symbols + 1) / 2;
+            let bytes = data
+                .get(1..1 + weight_bytes)
+                .ok_or(ParseError::UnsupportedFeature("zstd: truncated Huffman weights"))?;
+            let mut weights = Vec::with_capacity(n_symbols + 1);
+            for i in 0..n_symbols {
+                let byte = bytes[i / 2];
+                let w = if i % 2 == 0 { byte >> 4 } else { byte & 0xF };
+                weights.push(w);
+            }
+            Ok((Self::from_weights(weights)?, 1 + weight_bytes))
+        } else {
+            let fse_bytes = data
+                .get(1..1 + header as usize)
+                .ok_or(ParseError::UnsupportedFeature("zstd: truncated Huffman weights"))?;
+            let mut fwd = ForwardBitReader::new(fse_bytes);
+            let (norm, log) = read_ncount(&mut fwd, HUF_MAX_WEIGHT)?;
+            let table = FseTable::from_normalized(&norm, log);
+            let weight_bits = &fse_bytes[fwd.bytes_consumed()..];
+            let weights = decode_huffman_weights(&table, weight_bits)?;
+            Ok((Self::from_weights(weights)?, 1 + header as usize))
+        }
+    }
+
+    fn from_weights(mut weights: Vec<u8>) -> Result<Self, ParseError> {
+        let total: u32 = weights
+            .iter()
+            .filter(|&&w| w > 0)
+            .map(|&w| 1u32 << (w - 1))
+            .sum();
+        if total == 0 {
+            return Err(ParseError::UnsupportedFeature("zstd: empty Huffman table"));
+        }
+        let table_log = highest_bit(total) + 1;
+        let rest = (1u32 << table_log) - total;
+        let last_weight = highest_bit(rest) + 1;
+        weights.push(last_weight);
+
+        let table_size = 1usize << table_log;
+        let mut rank_count = vec![0u32; table_log as usize + 2];
+        for &w in &weights {
+            if w > 0 {
+                rank_count[(table_log + 1 - w) as usize] += 1;
+            }
+        }
+        let mut next_start = vec![0u32; table_log as usize + 2];
+        let mut cursor = 0u32;
+        for nb in (1..=table_log).rev() {
+            next_start[nb as usize] = cursor;
+            cursor += rank_count[nb as usize] * (1 << (table_log - nb));
+        }
+
+        let mut table = vec![(0u8, 0u8); table_size];
+        for (symbol, &w) in weights.iter().enumerate() {
+            if w == 0 {
+                continue;
+            }
+            let nb = table_log + 1 - w;
+            let span = 1u32 << (table_log - nb);
+            let start = next_start[nb as usize];
+            for slot in start..start + span {
+                table[slot as usize] = (symbol as u8, nb);
+            }
+            next_start[nb as usize] += span;
+        }
+
+        Ok(Self { table, table_log })
+    }
+
+    fn decode(&self, data: &[u8], out_len: usize) -> Result<Vec<u8>, ParseError> {
+        if out_len == 0 {
+            return Ok(Vec::new());
+        }
+        let mut bits = ReverseBitReader::new(data)?;
+        let mut out = Vec::with_capacity(out_len);
+        for _ in 0..out_len {
+            let peek = bits.peek(self.table_log as u32);
+            let (symbol, nb_bits) = self.table[peek as usize];
+            out.push(symbol);
+            bits.consume(nb_bits as u32);
+        }
+        Ok(out)
+    }
+}
+
+fn decode_huffman_weights(table: &FseTable, data: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let mut bits = ReverseBitReader::new(data)?;
+    let mut state = bits.read(table.accuracy_log as u32) as usize;
+    let mut weights = Vec::new();
+    // The number of weights isn't transmitted separately: decoding continues
+    // until the bitstream is exhausted, the same convention as any other
+    // single-symbol FSE stream with an implicit length.
+    loop {
+        let (symbol, nb_bits, base) = table.entries[state];
+        weights.push(symbol);
+        if bits.exhausted() {
+            break;
+        }
+        state = base as usize + bits.read(nb_bits as u32) as usize;
+    }
+    Ok(weights)
+}
+
+// --- Bit readers ------------------------------------------------------------
+
+/// Reads bits forward from the start of a buffer, LSB-first within each
+/// byte, as used by FSE/Huffman table *descriptions* and the
+/// Number_of_Sequences/Symbol_Compression_Modes header (as opposed to the
+/// symbol bitstreams themselves, which are read backward).
+struct ForwardBitReader<'a> {
+    data: &'a [u8],
+    bit_pos: usize,
+}
+
+impl<'a> ForwardBitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, bit_pos: 0 }
+    }
+
+    fn read(&mut self, n: u32) -> u32 {
+        let mut v = 0u32;
+        for k in 0..n {
+            let byte_idx = self.bit_pos / 8;
+            let bit = if byte_idx < self.data.len() {
+                (self.data[byte_idx] >> (self.bit_pos % 8)) & 1
+            } else {
+                0
+            };
+            v |= (bit as u32) << k;
+            self.bit_pos += 1;
+        }
+        v
+    }
+
+    fn read_byte(&mut self) -> Result<u8, ParseError> {
+        // Byte-oriented reads (nb-of-sequences / symbol-compression-mode
+        // headers) only ever occur on a byte boundary in this decoder.
+        debug_assert_eq!(self.bit_pos % 8, 0);
+        let idx = self.bit_pos / 8;
+        let byte = *self
+            .data
+            .get(idx)
+            .ok_or(ParseError::UnsupportedFeature("zstd: truncated sequences header"))?;
+        self.bit_pos += 8;
+        Ok(byte)
+    }
+
+    fn bytes_consumed(&self) -> usize {
+        (self.bit_pos + 7) / 8
+    }
+}
+
+/// Reads bits backward from the end of a buffer, as used by FSE and Huffman
+/// symbol bitstreams (RFC 8878 section 4, "Bitstream"). The buffer's last
+/// byte carries a single `1` padding bit at its highest set position,
+/// marking the logical end of the stream.
+struct ReverseBitReader<'a> {
+    data: &'a [u8],
+    bit_pos: isize,
+}
+
+impl<'a> ReverseBitReader<'a> {
+    fn new(data: &'a [u8]) -> Result<Self, ParseError> {
+        let last = *data
+            .last()
+            .ok_or(ParseError::UnsupportedFeature("zstd: empty bitstream"))?;
+        if last == 0 {
+            return Err(ParseError::UnsupportedFeature("zstd: missing bitstream padding bit"));
+        }
+        let top_bit = 7 - last.leading_zeros() as isize;
+        let bit_pos = (data.len() as isize - 1) * 8 + top_bit - 1;
+        Ok(Self { data, bit_pos })
+    }
+
+    fn bit(&self, i: isize) -> u32 {
+        if i < 0 {
+            return 0;
+        }
+        let byte = self.data[(i / 8) as usize];
+        ((byte >> (i % 8)) & 1) as u32
+    }
+
+    fn peek(&self, n: u32) -> u32 {
+        let mut v = 0u32;
+        let mut p = self.bit_pos;
+        for _ in 0..n {
+            v = (v << 1) | self.bit(p);
+            p -= 1;
+        }
+        v
+    }
+
+    fn consume(&mut self, n: u32) {
+        self.bit_pos -= n as isize;
+    }
+
+    fn read(&mut self, n: u32) -> u32 {
+        let v = self.peek(n);
+        self.consume(n);
+        v
+    }
+
+    fn exhausted(&self) -> bool {
+        self.bit_pos < 0
+    }
+}
+
+/// A byte cursor over a single frame/block's worth of Zstandard input.
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+        if self.remaining() < len {
+            return Err(ParseError::UnsupportedFeature(
+                "zstd: truncated frame",
+            ));
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), ParseError> {
+        self.read_bytes(len).map(|_| ())
+    }
+
+    fn read_u8(&mut self) -> Result<u8, ParseError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u24(&mut self) -> Result<u32, ParseError> {
+        let b = self.read_bytes(3)?;
+        Ok(b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ParseError> {
+        let b = self.read_bytes(4)?;
+        Ok(b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16 | (b[3] as u32) << 24)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a single-segment, no-checksum, no-dictionary frame with one
+    /// block, whose frame content size field is one byte (valid as long as
+    /// the frame's total decompressed size is under 256 bytes).
+    fn frame_bytes(block_type: u8, block_size: u16, block_data: &[u8], content_size: u8) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&MAGIC_NUMBER.to_le_bytes());
+        data.push(0b0010_0000); // single_segment, fcs_flag = 0 => 1-byte fcs
+        data.push(content_size);
+        let header = ((block_size as u32) << 3) | ((block_type as u32) << 1) | 1; // last_block
+        data.extend_from_slice(&header.to_le_bytes()[0..3]);
+        data.extend_from_slice(block_data);
+        data
+    }
+
+    #[test]
+    fn decodes_raw_block() {
+        let payload = b"hello world";
+        let frame = frame_bytes(0, payload.len() as u16, payload, payload.len() as u8);
+        let mut out = vec![0u8; payload.len()];
+        decode_frame(&frame, &mut out).unwrap();
+        assert_eq!(out, payload);
+    }
+
+    #[test]
+    fn decodes_rle_block() {
+        let repeat_count = 5u16;
+        let frame = frame_bytes(1, repeat_count, &[b'x'], repeat_count as u8);
+        let mut out = vec![0u8; repeat_count as usize];
+        decode_frame(&frame, &mut out).unwrap();
+        assert_eq!(out, vec![b'x'; repeat_count as usize]);
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut frame = frame_bytes(0, 1, &[0], 1);
+        frame[0] = 0;
+        let mut out = vec![0u8; 1];
+        assert!(matches!(
+            decode_frame(&frame, &mut out),
+            Err(ParseError::UnsupportedFeature(_))
+        ));
+    }
+
+    // The Compressed_Block (FSE sequences / Huffman literals) path has no
+    // test here: producing a real Zstandard-compressed fixture requires the
+    // actual `zstd` encoder, which this sandbox doesn't have access to, and
+    // hand-authoring FSE/Huffman bitstreams byte-by-byte would only prove
+    // this decoder agrees with itself, not with the format. Exercise that
+    // path against real `zstd -19` output (and ideally a reference decoder)
+    // before relying on it.
+}